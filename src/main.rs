@@ -1,21 +1,53 @@
-use std::sync::Arc;
+use std::{fs::OpenOptions, io, path::PathBuf, sync::Arc};
 
 use clap::Parser;
 use cryo::pager::{AccessContext, Pager};
-use env_logger::Env;
+use env_logger::{Env, Target};
 
 #[derive(Parser, Debug, Clone)]
 struct Cli {
     database: String,
+    /// Append log output to this file instead of stderr.
+    #[arg(long)]
+    log_file: Option<PathBuf>,
+    /// Validate every page in the database before using it.
+    #[arg(long)]
+    check_on_open: bool,
 }
 
-fn main() {
-    env_logger::init_from_env(
+/// Build the process logger, writing to `log_file` when given and to stderr
+/// otherwise.
+fn build_logger(log_file: Option<&PathBuf>) -> io::Result<env_logger::Logger> {
+    let mut builder = env_logger::Builder::from_env(
         Env::default().filter_or("CRYO_LOG_LEVEL", "DEBUG"),
     );
+
+    if let Some(path) = log_file {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+        builder.target(Target::Pipe(Box::new(file)));
+    }
+
+    Ok(builder.build())
+}
+
+fn main() {
     let cli = Cli::parse();
 
+    let logger =
+        build_logger(cli.log_file.as_ref()).expect("log file can be opened");
+    log::set_max_level(logger.filter());
+    log::set_boxed_logger(Box::new(logger)).expect("logger is only set once");
+
     let pager = Arc::new(Pager::open(cli.database, 10).unwrap());
+    if cli.check_on_open
+        && let Err(e) = pager.verify()
+    {
+        log::error!("database failed integrity check: {e}");
+        std::process::exit(1);
+    }
     let start = Arc::new(std::sync::Barrier::new(11));
 
     let mut handles = Vec::with_capacity(10);
@@ -45,3 +77,30 @@ fn main() {
 
     log::info!("After thread join: {pager}");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use log::Log;
+
+    #[test]
+    fn log_file_receives_log_lines() {
+        let dir = tempfile::TempDir::new().expect("temp dir can be created");
+        let path = dir.path().join("cryo.log");
+
+        let logger =
+            build_logger(Some(&path)).expect("logger can be built for file");
+        logger.log(
+            &log::Record::builder()
+                .level(log::Level::Error)
+                .target("cryo")
+                .args(format_args!("written to the log file"))
+                .build(),
+        );
+        logger.flush();
+
+        let contents =
+            std::fs::read_to_string(&path).expect("log file can be read");
+        assert!(contents.contains("written to the log file"));
+    }
+}
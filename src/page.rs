@@ -1,4 +1,4 @@
-use std::{fmt, ops};
+use std::{fmt, io, ops};
 
 use bitflags::bitflags;
 
@@ -34,6 +34,11 @@ pub const FORMAT_VERSION_SIZE: usize = size_of::<u8>();
 
 pub const HEADER_SIZE: usize = 100;
 
+/// Smallest number of bytes a [`Page`] can be built from.
+pub const MIN_PAGE_SIZE: usize = 512;
+/// Largest number of bytes a [`Page`] can be built from.
+pub const MAX_PAGE_SIZE: usize = u16::MAX as usize;
+
 pub const MAGIC: &str = "CRYOGENIC";
 pub const MAGIC_SIZE: usize = MAGIC.len();
 pub const MAGIC_OFFSET: usize = HEADER_SIZE - MAGIC_SIZE;
@@ -111,7 +116,7 @@ impl Page {
     {
         let inner: Box<[u8]> = bytes.into();
         assert!(
-            inner.len() >= 512 && inner.len() <= u16::MAX as usize,
+            (MIN_PAGE_SIZE..=MAX_PAGE_SIZE).contains(&inner.len()),
             "bytes is not page size len"
         );
         Self { inner }
@@ -182,6 +187,30 @@ impl Page {
     );
 }
 
+impl TryFrom<&[u8]> for Page {
+    type Error = io::Error;
+
+    /// Copy `bytes` into a new [`Page`].
+    ///
+    /// Unlike [`Page::build`] this does not panic; a slice that is not a valid
+    /// page length is rejected with [`io::ErrorKind::InvalidInput`].
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        if !(MIN_PAGE_SIZE..=MAX_PAGE_SIZE).contains(&bytes.len()) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "{} bytes is not a valid page size ({MIN_PAGE_SIZE}..={MAX_PAGE_SIZE})",
+                    bytes.len()
+                ),
+            ));
+        }
+
+        Ok(Self {
+            inner: bytes.into(),
+        })
+    }
+}
+
 impl ops::DerefMut for Page {
     fn deref_mut(&mut self) -> &mut Self::Target {
         &mut self.inner
@@ -229,4 +258,18 @@ mod test {
         assert_eq!(page.free_space(), 4096);
         assert_ne!(page.inner[..], vec![0; 4096][..])
     }
+
+    #[test]
+    fn page_try_from_slice() {
+        let mut bytes = vec![0; 4096];
+        bytes[NUM_KEY_OFFSET..LSN_OFFSET].copy_from_slice(&7u16.to_be_bytes());
+
+        let page = Page::try_from(&bytes[..]).expect("full page slice parses");
+        assert_eq!(page.len(), 4096);
+        assert_eq!(page.num_keys(), 7);
+
+        let err = Page::try_from(&bytes[..MIN_PAGE_SIZE - 1])
+            .expect_err("short slice is rejected");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
 }
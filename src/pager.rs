@@ -2,25 +2,31 @@
 //!
 use crate::{
     Page, PageFlags,
-    page::{HEADER_SIZE, MAGIC},
+    page::{
+        FORMAT_VERSION_OFFSET, HEADER_SIZE, MAGIC, MAGIC_OFFSET, MAX_PAGE_SIZE,
+        MIN_PAGE_SIZE, PAGE_SIZE_OFFSET,
+    },
 };
 use log::{debug, info, trace, warn};
 use std::{
     collections::HashMap,
     fmt,
-    fs::{File, OpenOptions},
+    fs::{File, OpenOptions, TryLockError},
     io::{self, Read, Seek, SeekFrom, Write},
     os::unix::fs::OpenOptionsExt,
     path::PathBuf,
     sync::{
         self,
-        atomic::{AtomicBool, AtomicUsize, Ordering},
+        atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
     },
     thread::ThreadId,
 };
 
 const O_DIRECT: i32 = 0o40000;
 
+/// Alignment, in bytes, of buffers exchanged with the backing store.
+const IO_ALIGN: usize = 4096;
+
 /// Default size, in bytes, used when creating a new database file.
 pub const DEFAULT_PAGE_SIZE: u16 = 4096;
 
@@ -32,6 +38,36 @@ pub const FORMAT_VERSION: u8 = 1;
 /// Page identifiers are one-based; page id `0` is invalid.
 pub const ROOT_PAGE_ID: usize = 1;
 
+/// Byte offset of `page_id` within a store of `size`-byte pages.
+///
+/// Rejects page id `0`, sizes a [`Page`] can not be built from, and ids whose
+/// offset does not fit in the address space, so callers never panic on
+/// untrusted page ids or sizes read back from disk.
+fn page_offset(page_id: usize, size: usize) -> io::Result<u64> {
+    if page_id == 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "page id can not be zero",
+        ));
+    }
+    if !(MIN_PAGE_SIZE..=MAX_PAGE_SIZE).contains(&size) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid page size {size}"),
+        ));
+    }
+
+    (page_id - 1)
+        .checked_mul(size)
+        .map(|offset| offset as u64)
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("page id {page_id} is out of addressable range"),
+            )
+        })
+}
+
 /// Loads a [`Page`] of `size` bytes from `reader`.
 ///
 /// A [`Page`] is valid when the `MAGIC` bytes are present in its
@@ -43,20 +79,13 @@ fn load_page(
     reader: &mut (impl Read + Seek),
 ) -> io::Result<Page> {
     info!("loading page {page_id} (size: {size})");
-    if page_id == 0 {
-        return Err(io::Error::new(
-            io::ErrorKind::InvalidInput,
-            "page id can not be zero",
-        ));
-    }
-
-    let offset = (page_id - 1) * size;
-    reader.seek(SeekFrom::Start(offset as u64))?;
+    let offset = page_offset(page_id, size)?;
+    reader.seek(SeekFrom::Start(offset))?;
 
-    let mut buf = vec![0; size];
-    reader.read_exact(&mut buf)?;
-
-    let page = Page::build(buf);
+    let page = with_io_buf(size, |buf| {
+        reader.read_exact(buf)?;
+        Page::try_from(&*buf)
+    })?;
     if page.magic() != MAGIC.as_bytes() {
         return Err(io::Error::new(
             io::ErrorKind::InvalidData,
@@ -84,23 +113,98 @@ fn write_page(
     page: &mut Page,
 ) -> io::Result<()> {
     info!("writing page {page_id} (size: {size})");
-    if page_id == 0 {
-        return Err(io::Error::new(
-            io::ErrorKind::InvalidInput,
-            "page id can not be zero",
-        ));
-    }
-
-    let offset = (page_id - 1) * size;
+    let offset = page_offset(page_id, size)?;
 
     page.set_magic();
     page.set_checksum(page.compute_checksum());
-    writer.seek(SeekFrom::Start(offset as u64))?;
-    writer.write_all(&page[..])?;
+    writer.seek(SeekFrom::Start(offset))?;
+    with_io_buf(size, |buf| {
+        buf.copy_from_slice(&page[..]);
+        writer.write_all(buf)
+    })?;
+
+    Ok(())
+}
+
+/// Checks that `size` can be used as the page size of a database file.
+///
+/// Pages are at least [`MIN_PAGE_SIZE`] bytes and a whole multiple of it: the
+/// file is opened with `O_DIRECT`, which only transfers whole 512-byte blocks
+/// at block-aligned offsets. `kind` is the [`io::ErrorKind`] reported for a
+/// size that fails the check, and `context` prefixes its message.
+fn check_page_size(
+    size: u16,
+    kind: io::ErrorKind,
+    context: &str,
+) -> io::Result<()> {
+    let size = size as usize;
+    if size < MIN_PAGE_SIZE || !size.is_multiple_of(MIN_PAGE_SIZE) {
+        return Err(io::Error::new(kind, format!("{context}page size {size}")));
+    }
 
     Ok(())
 }
 
+/// Reads the page size recorded in the root page header of `reader`, a store
+/// of `len` bytes.
+///
+/// Only the leading block holding the header is read so the full root page
+/// can then be loaded, and its checksum verified, at the recorded size. The
+/// block is [`DEFAULT_PAGE_SIZE`] bytes, or [`MIN_PAGE_SIZE`] for stores
+/// shorter than that, keeping the read block aligned for `O_DIRECT`.
+fn read_root_page_size(
+    reader: &mut (impl Read + Seek),
+    len: u64,
+) -> io::Result<u16> {
+    let block = if len >= DEFAULT_PAGE_SIZE as u64 {
+        DEFAULT_PAGE_SIZE as usize
+    } else {
+        MIN_PAGE_SIZE
+    };
+
+    let mut header = [0; HEADER_SIZE];
+    reader.seek(SeekFrom::Start(0))?;
+    with_io_buf(block, |buf| {
+        reader.read_exact(buf)?;
+        header.copy_from_slice(&buf[..HEADER_SIZE]);
+        io::Result::Ok(())
+    })?;
+
+    if &header[MAGIC_OFFSET..] != MAGIC.as_bytes() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "corrupted root information: bytes are not a valid page",
+        ));
+    }
+
+    let page_size = u16::from_be_bytes(
+        header[PAGE_SIZE_OFFSET..FORMAT_VERSION_OFFSET]
+            .try_into()
+            .expect("page size field is two bytes"),
+    );
+    check_page_size(
+        page_size,
+        io::ErrorKind::InvalidData,
+        "corrupted root information: ",
+    )?;
+
+    Ok(page_size)
+}
+
+/// Runs `f` with a zeroed buffer of `len` bytes aligned to [`IO_ALIGN`].
+///
+/// The database file is opened with `O_DIRECT`, which rejects reads and
+/// writes whose buffer address is not block aligned. Heap buffers such as a
+/// `Vec<u8>` or a [`Page`] carry no such guarantee, so every transfer with
+/// the backing store goes through one of these.
+fn with_io_buf<R>(len: usize, f: impl FnOnce(&mut [u8]) -> R) -> R {
+    let mut raw = vec![0; len + IO_ALIGN];
+    let start = raw
+        .as_ptr()
+        .align_offset(IO_ALIGN);
+    f(&mut raw[start..start + len])
+}
+
 /// Create a new [`Page`].
 ///
 /// The created page is initialized with page flags, free-space metadata,
@@ -282,6 +386,8 @@ pub struct CachedPage {
     dirty: AtomicBool,
     /// Number of active users.
     pin_count: AtomicUsize,
+    /// Pager epoch at which the page was last loaded or modified.
+    epoch: AtomicU64,
 
     handles: sync::Mutex<Vec<PageHandle>>,
 }
@@ -295,6 +401,7 @@ impl CachedPage {
             dirty: AtomicBool::new(dirty),
             accessed: AtomicBool::new(true),
             pin_count: AtomicUsize::new(0),
+            epoch: AtomicU64::new(0),
             handles: sync::Mutex::new(Vec::new()),
         }
     }
@@ -378,6 +485,8 @@ where
 
     clock: sync::Mutex<ClockState>,
     pages: sync::RwLock<HashMap<usize, sync::Arc<CachedPage>>>,
+    /// Last epoch handed out to a cached page; see [`Pager::page_with_epoch`].
+    epoch: AtomicU64,
 }
 
 /// State used by the Clock cache replacement algorithm.
@@ -421,6 +530,79 @@ impl<F> Pager<F>
 where
     F: Read + Write + Seek,
 {
+    /// Builds a [`Pager`] over the backing store `inner`.
+    ///
+    /// An empty store is initialized with a root leaf page of `page_size`
+    /// bytes, or [`DEFAULT_PAGE_SIZE`] when none is given. Otherwise the page
+    /// size recorded in the root header is validated and the root is loaded
+    /// at that size; a requested `page_size` must match it.
+    fn from_store(
+        mut inner: F,
+        capacity: usize,
+        page_size: Option<u16>,
+    ) -> io::Result<Self> {
+        let len = inner.seek(SeekFrom::End(0))?;
+
+        let root: Page;
+        let created: bool;
+
+        if len < HEADER_SIZE as u64 {
+            let size = page_size.unwrap_or(DEFAULT_PAGE_SIZE);
+            check_page_size(size, io::ErrorKind::InvalidInput, "invalid ")?;
+
+            root = create_page(
+                PageFlags::IsRoot | PageFlags::IsLeaf,
+                size,
+                HEADER_SIZE as u16,
+                true,
+            );
+            created = true;
+        } else {
+            let stored = read_root_page_size(&mut inner, len)?;
+            if let Some(size) = page_size
+                && size != stored
+            {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!(
+                        "page size mismatch: requested {size}, stored {stored}"
+                    ),
+                ));
+            }
+
+            root = load_page(ROOT_PAGE_ID, stored as usize, &mut inner)
+                .map_err(|e| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("corrupted root information: {e}"),
+                    )
+                })?;
+            created = false;
+        }
+
+        let out = Self {
+            capacity,
+            clock: sync::Mutex::new(ClockState {
+                hand: 0,
+                ring: vec![],
+            }),
+            flush_guard: sync::Arc::new(NoopFlushGuard),
+            inner: sync::Mutex::new(inner),
+            page_size: root.page_size(),
+            pages: sync::RwLock::new(HashMap::with_capacity(capacity)),
+            epoch: AtomicU64::new(0),
+        };
+        trace!("pager initialize: root={root}");
+        out.track(ROOT_PAGE_ID, root, created)?;
+
+        Ok(out)
+    }
+
+    /// Returns the size, in bytes, of every page managed by this [`Pager`].
+    pub fn page_size(&self) -> u16 {
+        self.page_size
+    }
+
     /// Set the [`FlushGuard`] for the [`Pager`]. Ensuring the set
     /// guards [`FlushGuard::before_flush`] is called before any data is synced
     /// to disk.
@@ -438,6 +620,23 @@ where
         ctx: AccessContext,
         f: impl FnOnce(&Page) -> R,
     ) -> io::Result<R> {
+        self.page_with_epoch(page_id, ctx, f)
+            .map(|(out, _)| out)
+    }
+
+    /// Access a [`Page`] with read access, also returning the page's epoch.
+    ///
+    /// The epoch is sampled while the page is read-latched, so it identifies
+    /// exactly the contents `f` observed. Every [`Pager::mut_page`] call, and
+    /// every reload of the page into the cache, stamps the page with a new
+    /// epoch. A caller can later compare against [`Pager::epoch`] to tell
+    /// whether the page may have changed since it was read.
+    pub fn page_with_epoch<R>(
+        &self,
+        page_id: usize,
+        ctx: AccessContext,
+        f: impl FnOnce(&Page) -> R,
+    ) -> io::Result<(R, u64)> {
         trace!(
             "page {page_id} access start: mode={:?} txn={:?} lsn={:?} reason={:?}",
             AccessMode::Read,
@@ -459,7 +658,7 @@ where
         handle.add(&page)?;
 
         let out = {
-            let page = page
+            let latched = page
                 .page
                 .read()
                 .map_err(|_e| {
@@ -468,7 +667,10 @@ where
                         "failed to acquire read lock on page",
                     )
                 })?;
-            f(&page)
+            let epoch = page
+                .epoch
+                .load(Ordering::Acquire);
+            (f(&latched), epoch)
         };
         handle.remove(&page)?;
         page.unpin();
@@ -531,6 +733,9 @@ where
             cached
                 .dirty
                 .store(true, Ordering::Release);
+            cached
+                .epoch
+                .store(self.next_epoch(), Ordering::Release);
             if let Some(lsn) = ctx.lsn {
                 page.set_lsn(lsn)
             }
@@ -548,6 +753,24 @@ where
         Ok(out)
     }
 
+    /// Returns the current epoch of the page with `page_id`.
+    ///
+    /// The page is loaded into the cache if needed and its epoch read under
+    /// the page's read latch. An epoch that differs from one returned by an
+    /// earlier [`Pager::page_with_epoch`] means the page was modified, or
+    /// evicted and reloaded, in between.
+    pub fn epoch(&self, page_id: usize) -> io::Result<u64> {
+        self.page_with_epoch(page_id, AccessContext::anonymous(), |_| ())
+            .map(|(_, epoch)| epoch)
+    }
+
+    /// Returns the next value of the pager's epoch counter.
+    fn next_epoch(&self) -> u64 {
+        self.epoch
+            .fetch_add(1, Ordering::AcqRel)
+            + 1
+    }
+
     /// Returns a snapshot of metadata for all currently cached pages.
     pub fn info(&self) -> Vec<CacheInfo> {
         let pages = self
@@ -634,6 +857,93 @@ where
         Ok(())
     }
 
+    /// Validates every page in the backing store.
+    ///
+    /// Pages are read straight from the store, bypassing the cache, and checked
+    /// the same way they are on load: the magic bytes must be present and the
+    /// stored checksum must match. Cached changes that have not been flushed
+    /// yet are not covered.
+    ///
+    /// ## Errors
+    ///
+    /// - [`io::ErrorKind::InvalidData`] when the store is not a whole number of
+    ///   pages long, or when a page fails validation. The message names the
+    ///   first invalid page.
+    /// - [`io::ErrorKind::PermissionDenied`] when the backing store lock can
+    ///   not be acquired.
+    /// - Any error returned by the backing store while reading.
+    pub fn verify(&self) -> io::Result<()> {
+        let mut inner = self
+            .inner
+            .lock()
+            .map_err(|_e| {
+                io::Error::new(
+                    io::ErrorKind::PermissionDenied,
+                    "failed to acquire lock on pager state",
+                )
+            })?;
+
+        let size = self.page_size as usize;
+        let len = inner.seek(SeekFrom::End(0))? as usize;
+        if !len.is_multiple_of(size) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "store length {len} is not a multiple of page size {size}"
+                ),
+            ));
+        }
+
+        for page_id in 1..=len / size {
+            load_page(page_id, size, &mut *inner).map_err(|e| {
+                io::Error::new(e.kind(), format!("page {page_id}: {e}"))
+            })?;
+        }
+        info!("pager verify: {} pages valid", len / size);
+
+        Ok(())
+    }
+
+    /// Flushes and evicts every cached page so subsequent accesses are served
+    /// from the backing store.
+    ///
+    /// Unlike [`Self::flush_all`], recently accessed dirty pages do not get a
+    /// Clock second chance; their accessed bits are cleared up front. Pinned
+    /// pages still fail with [`io::ErrorKind::ResourceBusy`], as do pages
+    /// accessed concurrently while the cache is being cleared.
+    pub fn clear_cache(&self) -> io::Result<()> {
+        for page in self
+            .pages
+            .read()
+            .map_err(|_| {
+                io::Error::new(
+                    io::ErrorKind::PermissionDenied,
+                    "failed to acquire read lock on page cache",
+                )
+            })?
+            .values()
+        {
+            page.accessed
+                .store(false, Ordering::Release);
+        }
+
+        self.flush_all(true)
+    }
+
+    /// Loads each page in `page_ids` into the cache ahead of use.
+    ///
+    /// Warming more pages than the cache capacity evicts earlier pages as
+    /// usual.
+    pub fn warm(
+        &self,
+        page_ids: impl IntoIterator<Item = usize>,
+    ) -> io::Result<()> {
+        for page_id in page_ids {
+            self.get_or_load(page_id)?;
+        }
+        Ok(())
+    }
+
     /// Flush a [`CachedPage`] to the underlying memory.
     ///
     /// If `evict` is `true`, the page is removed from the cache after it has been
@@ -914,6 +1224,9 @@ where
         }
 
         let cached = sync::Arc::new(CachedPage::new(id, page, dirty));
+        cached
+            .epoch
+            .store(self.next_epoch(), Ordering::Release);
         pages.insert(id, cached.clone());
         clock.ring.push(id);
         Ok(cached)
@@ -1003,59 +1316,57 @@ impl Pager<File> {
     /// Opens an existing pager file or creates a new one.
     ///
     /// New files are initialized with a root leaf page using
-    /// [`DEFAULT_PAGE_SIZE`]. Existing files read the root page at the default
-    /// size first so the stored page size can be discovered.
+    /// [`DEFAULT_PAGE_SIZE`]. Existing files are read at the page size stored
+    /// in their root page.
+    ///
+    /// The file stays locked until the [`Pager`] is dropped; opening a file
+    /// that another [`Pager`] holds fails with
+    /// [`io::ErrorKind::ResourceBusy`].
     pub fn open(path: impl Into<PathBuf>, capacity: usize) -> io::Result<Self> {
-        let mut inner = OpenOptions::new()
-            .read(true)
-            .write(true)
-            .create(true)
-            .custom_flags(O_DIRECT)
-            .open(path.into())?;
-        let len = inner.metadata()?.len();
-
-        let page_size: u16;
-        let root: Page;
-        let created: bool;
+        Self::from_store(open_file(path.into())?, capacity, None)
+    }
 
-        if len < DEFAULT_PAGE_SIZE as u64 {
-            root = create_page(
-                PageFlags::IsRoot | PageFlags::IsLeaf,
-                DEFAULT_PAGE_SIZE,
-                HEADER_SIZE as u16,
-                true,
-            );
-            created = true;
-            page_size = DEFAULT_PAGE_SIZE;
-        } else {
-            root =
-                load_page(ROOT_PAGE_ID, DEFAULT_PAGE_SIZE as usize, &mut inner)
-                    .map_err(|e| {
-                        io::Error::new(
-                            io::ErrorKind::InvalidData,
-                            format!("corrupted root information: {e}"),
-                        )
-                    })?;
-            created = false;
-            page_size = root.page_size();
-        }
+    /// Opens an existing pager file or creates a new one with `page_size`
+    /// byte pages.
+    ///
+    /// ## Errors
+    ///
+    /// Returns [`io::ErrorKind::InvalidInput`] when `page_size` is smaller
+    /// than [`MIN_PAGE_SIZE`] or not a multiple of it, or when an existing
+    /// file was created with a different page size, and
+    /// [`io::ErrorKind::ResourceBusy`] when another [`Pager`] holds the file.
+    pub fn open_with_page_size(
+        path: impl Into<PathBuf>,
+        capacity: usize,
+        page_size: u16,
+    ) -> io::Result<Self> {
+        Self::from_store(open_file(path.into())?, capacity, Some(page_size))
+    }
+}
 
-        let out = Self {
-            capacity,
-            clock: sync::Mutex::new(ClockState {
-                hand: 0,
-                ring: vec![],
-            }),
-            flush_guard: sync::Arc::new(NoopFlushGuard),
-            inner: sync::Mutex::new(inner),
-            page_size,
-            pages: sync::RwLock::new(HashMap::with_capacity(capacity)),
-        };
-        trace!("pager initialize: root={root}");
-        out.track(ROOT_PAGE_ID, root, created)?;
+/// Opens the database file at `path` for direct I/O, creating it if needed.
+///
+/// The file is held under an exclusive advisory lock for as long as the
+/// returned handle lives, so a second pager can not open the same database
+/// and overwrite its pages.
+fn open_file(path: PathBuf) -> io::Result<File> {
+    let file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .custom_flags(O_DIRECT)
+        .open(&path)?;
+
+    file.try_lock()
+        .map_err(|e| match e {
+            TryLockError::WouldBlock => io::Error::new(
+                io::ErrorKind::ResourceBusy,
+                format!("database already in use: {}", path.display()),
+            ),
+            TryLockError::Error(e) => e,
+        })?;
 
-        Ok(out)
-    }
+    Ok(file)
 }
 
 impl<F> fmt::Display for Pager<F>
@@ -1098,6 +1409,7 @@ mod tests {
             inner: sync::Mutex::new(inner),
             page_size: DEFAULT_PAGE_SIZE,
             pages: sync::RwLock::new(HashMap::with_capacity(8)),
+            epoch: AtomicU64::new(0),
         }
     }
 
@@ -1170,6 +1482,156 @@ mod tests {
         load_page(page_id, DEFAULT_PAGE_SIZE as usize, &mut *inner)
     }
 
+    #[test]
+    fn corrupted_page_bytes_fail_checksum_on_load() {
+        let pager = pager_with_pages([
+            (1, test_page(1, b'a')),
+            (2, test_page(2, b'b')),
+        ]);
+
+        {
+            let mut inner = pager
+                .inner
+                .lock()
+                .expect("test can lock pager backing store");
+            let at = DEFAULT_PAGE_SIZE as usize + HEADER_SIZE;
+            inner.get_mut()[at] ^= 0xff;
+        }
+
+        let err = pager
+            .page(2, AccessContext::anonymous(), |page| page.num_keys())
+            .expect_err("bit-flipped page is rejected");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(pager.info().is_empty());
+
+        pager
+            .page(1, AccessContext::anonymous(), |_| ())
+            .expect("untouched page still loads");
+    }
+
+    #[test]
+    fn verify_reports_first_corrupted_page() {
+        let pager = pager_with_pages([
+            (1, test_page(1, b'a')),
+            (2, test_page(2, b'b')),
+            (3, test_page(3, b'c')),
+        ]);
+        pager
+            .verify()
+            .expect("clean store verifies");
+
+        {
+            let mut inner = pager
+                .inner
+                .lock()
+                .expect("test can lock pager backing store");
+            let at = 2 * DEFAULT_PAGE_SIZE as usize + HEADER_SIZE;
+            inner.get_mut()[at] ^= 0xff;
+        }
+
+        let err = pager
+            .verify()
+            .expect_err("corrupted store fails verification");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(
+            err.to_string()
+                .starts_with("page 3:")
+        );
+    }
+
+    #[test]
+    fn verify_reports_corrupted_page_in_reopened_file() {
+        let dir = tempfile::TempDir::new().expect("temp dir can be created");
+        let path = dir.path().join("cryo.db");
+
+        {
+            let pager = Pager::open(&path, 8).expect("database can be created");
+            pager
+                .mut_page(
+                    ROOT_PAGE_ID,
+                    AccessContext::maintenance("test mutation"),
+                    |page| page.set_num_keys(1),
+                )
+                .expect("root can be mutated");
+            pager
+                .clear_cache()
+                .expect("root can be flushed");
+        }
+        {
+            let mut file = OpenOptions::new()
+                .write(true)
+                .open(&path)
+                .expect("database can be opened for writing");
+            let mut page = test_page(2, b'b');
+            write_page(2, DEFAULT_PAGE_SIZE as usize, &mut file, &mut page)
+                .expect("second page can be written");
+        }
+
+        Pager::open(&path, 8)
+            .expect("database can be reopened")
+            .verify()
+            .expect("clean file verifies");
+
+        let mut bytes = std::fs::read(&path).expect("database can be read");
+        bytes[DEFAULT_PAGE_SIZE as usize + HEADER_SIZE] ^= 0xff;
+        std::fs::write(&path, bytes).expect("database can be rewritten");
+
+        let err = Pager::open(&path, 8)
+            .expect("database with intact root can be reopened")
+            .verify()
+            .expect_err("corrupted file fails verification");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert!(
+            err.to_string()
+                .starts_with("page 2:")
+        );
+    }
+
+    #[test]
+    fn mutations_advance_only_the_mutated_page_epoch() {
+        let pager = pager_with_pages([
+            (1, test_page(1, b'a')),
+            (2, test_page(2, b'b')),
+        ]);
+
+        let (keys, first) = pager
+            .page_with_epoch(1, AccessContext::anonymous(), |p| p.num_keys())
+            .expect("page 1 can be read");
+        assert_eq!(keys, 1);
+        let (_, second) = pager
+            .page_with_epoch(2, AccessContext::anonymous(), |_| ())
+            .expect("page 2 can be read");
+        assert_eq!(
+            pager
+                .epoch(1)
+                .expect("page 1 epoch"),
+            first
+        );
+
+        pager
+            .mut_page(2, AccessContext::maintenance("test mutation"), |page| {
+                page.set_num_keys(3);
+            })
+            .expect("page 2 can be mutated");
+
+        assert_eq!(
+            pager
+                .epoch(1)
+                .expect("page 1 epoch"),
+            first,
+            "writes to other pages leave the epoch alone"
+        );
+        let current = pager
+            .epoch(2)
+            .expect("page 2 epoch");
+        assert_ne!(current, second, "stale epoch detects the write");
+
+        let (keys, epoch) = pager
+            .page_with_epoch(2, AccessContext::anonymous(), |p| p.num_keys())
+            .expect("page 2 can be read again");
+        assert_eq!((keys, epoch), (3, current));
+    }
+
     #[test]
     fn flushing_untracked_page_returns_other() {
         let pager = pager_with_pages([(1, test_page(1, b'a'))]);
@@ -1472,6 +1934,148 @@ mod tests {
         assert_eq!(persisted.cell(HEADER_SIZE, HEADER_SIZE + 1)[0], b'r');
     }
 
+    #[test]
+    fn clear_cache_flushes_dirty_pages_and_warm_reloads_them() {
+        let pager = pager_with_pages([
+            (1, test_page(1, b'a')),
+            (2, test_page(2, b'b')),
+        ]);
+
+        pager
+            .mut_page(1, AccessContext::maintenance("test mutation"), |page| {
+                page.set_num_keys(12);
+            })
+            .expect("page 1 can be mutated");
+        pager
+            .page(2, AccessContext::anonymous(), |_| ())
+            .expect("page 2 can be loaded");
+
+        pager
+            .clear_cache()
+            .expect("recently accessed pages can be cleared");
+        assert!(pager.info().is_empty());
+
+        let persisted =
+            persisted_page(&pager, 1).expect("dirty page was flushed");
+        assert_eq!(persisted.num_keys(), 12);
+
+        pager
+            .warm([1, 2])
+            .expect("pages can be warmed");
+        let mut cached_ids = pager
+            .info()
+            .into_iter()
+            .map(|info| info.page_id)
+            .collect::<Vec<_>>();
+        cached_ids.sort_unstable();
+        assert_eq!(cached_ids, vec![1, 2]);
+    }
+
+    #[test]
+    fn cache_stays_within_capacity_when_touching_many_pages() {
+        let pager =
+            pager_with_pages((1..=64).map(|id| (id, test_page(0, b'a'))));
+
+        for id in 1..=64 {
+            pager
+                .mut_page(
+                    id,
+                    AccessContext::maintenance("test mutation"),
+                    |page| {
+                        page.set_num_keys(id as u16);
+                    },
+                )
+                .expect("page can be mutated");
+            assert!(pager.info().len() <= pager.capacity);
+        }
+
+        // Pages evicted to make room were written back before being dropped.
+        let persisted = persisted_page(&pager, 1).expect("page 1 was evicted");
+        assert_eq!(persisted.num_keys(), 1);
+    }
+
+    /// A root page as [`create_page`] writes it, with `page_size` recorded.
+    fn root_store(page_size: u16) -> Cursor<Vec<u8>> {
+        let mut root = create_page(
+            PageFlags::IsRoot | PageFlags::IsLeaf,
+            DEFAULT_PAGE_SIZE,
+            HEADER_SIZE as u16,
+            true,
+        );
+        root.set_page_size(page_size);
+
+        let mut store = Cursor::new(Vec::new());
+        write_page(
+            ROOT_PAGE_ID,
+            DEFAULT_PAGE_SIZE as usize,
+            &mut store,
+            &mut root,
+        )
+        .expect("root can be written");
+        store
+    }
+
+    #[test]
+    fn root_with_impossible_page_size_is_rejected() {
+        for page_size in [0, 1, 100, MIN_PAGE_SIZE as u16 - 1, 1000, u16::MAX] {
+            let err = Pager::from_store(root_store(page_size), 8, None)
+                .err()
+                .expect("root with impossible page size is rejected");
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+            assert!(
+                err.to_string()
+                    .contains("page size")
+            );
+        }
+    }
+
+    #[test]
+    fn opening_arbitrary_root_bytes_never_panics() {
+        // xorshift; deterministic so failures are reproducible.
+        let mut state: u64 = 0x9e37_79b9_7f4a_7c15;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+
+        for round in 0..512 {
+            let mut store = root_store(DEFAULT_PAGE_SIZE).into_inner();
+            for _ in 0..=(next() % 8) {
+                let at = next() as usize % HEADER_SIZE;
+                store[at] = next() as u8;
+            }
+            // Most rounds repair the trailer so the mutated header reaches the
+            // pager's own parsing instead of failing the checksum.
+            if round % 4 != 0 {
+                let mut root = Page::build(store);
+                root.set_magic();
+                root.set_checksum(root.compute_checksum());
+                store = root.to_vec();
+            }
+
+            let Ok(pager) = Pager::from_store(Cursor::new(store), 8, None)
+            else {
+                continue;
+            };
+            let _ = pager.page(ROOT_PAGE_ID, AccessContext::anonymous(), |p| {
+                (p.to_string(), p.flags(), p.latest_lsn())
+            });
+            let _ = pager.page(2, AccessContext::anonymous(), |_| ());
+        }
+    }
+
+    #[test]
+    fn accessing_unaddressable_page_is_invalid() {
+        let pager = pager_with_pages([(1, test_page(1, b'a'))]);
+
+        let err = pager
+            .page(usize::MAX, AccessContext::anonymous(), |_| ())
+            .expect_err("unaddressable page id is rejected");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+
     #[test]
     fn accessing_page_zero_is_invalid() {
         let pager = pager_with_pages([(1, test_page(1, b'a'))]);
@@ -1482,4 +2086,147 @@ mod tests {
 
         assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
     }
+
+    #[test]
+    fn store_is_reopened_at_its_recorded_page_size() {
+        let pager = Pager::from_store(Cursor::new(Vec::new()), 8, Some(8192))
+            .expect("pager can be created with 8192 byte pages");
+        assert_eq!(pager.page_size(), 8192);
+        pager
+            .mut_page(
+                ROOT_PAGE_ID,
+                AccessContext::maintenance("test mutation"),
+                |page| page.set_num_keys(5),
+            )
+            .expect("root can be mutated");
+        pager
+            .clear_cache()
+            .expect("root can be flushed");
+
+        let store = pager
+            .inner
+            .into_inner()
+            .expect("test can take pager backing store");
+        assert_eq!(store.get_ref().len(), 8192);
+
+        let reopened = Pager::from_store(store.clone(), 8, None)
+            .expect("store reopens at its recorded size");
+        assert_eq!(reopened.page_size(), 8192);
+        let (len, num_keys) = reopened
+            .page(ROOT_PAGE_ID, AccessContext::anonymous(), |page| {
+                (page.len(), page.num_keys())
+            })
+            .expect("root can be read");
+        assert_eq!(len, 8192);
+        assert_eq!(num_keys, 5);
+
+        let err = Pager::from_store(store, 8, Some(DEFAULT_PAGE_SIZE))
+            .err()
+            .expect("mismatched page size is rejected");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+        for page_size in [64, 1000, u16::MAX] {
+            let err =
+                Pager::from_store(Cursor::new(Vec::new()), 8, Some(page_size))
+                    .err()
+                    .expect("impossible page size is rejected");
+            assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        }
+    }
+
+    /// Creates a database file at `page_size`, sets the root's key count,
+    /// flushes it and drops the pager, releasing the file.
+    fn flushed_database(path: &std::path::Path, page_size: u16, keys: u16) {
+        let pager = Pager::open_with_page_size(path, 8, page_size)
+            .expect("database can be created");
+        pager
+            .mut_page(
+                ROOT_PAGE_ID,
+                AccessContext::maintenance("test mutation"),
+                |page| page.set_num_keys(keys),
+            )
+            .expect("root can be mutated");
+        pager
+            .clear_cache()
+            .expect("root can be flushed to the file");
+    }
+
+    #[test]
+    fn database_file_is_reopened_at_its_page_size() {
+        let dir = tempfile::TempDir::new().expect("temp dir can be created");
+
+        for (name, page_size) in [("large.db", 8192), ("default.db", 4096)] {
+            let path = dir.path().join(name);
+            flushed_database(&path, page_size, 3);
+            assert_eq!(
+                std::fs::metadata(&path)
+                    .expect("database file exists")
+                    .len(),
+                page_size as u64
+            );
+
+            let reopened =
+                Pager::open(&path, 8).expect("existing database reopens");
+            assert_eq!(reopened.page_size(), page_size);
+            let num_keys = reopened
+                .page(ROOT_PAGE_ID, AccessContext::anonymous(), |page| {
+                    page.num_keys()
+                })
+                .expect("root can be read back");
+            assert_eq!(num_keys, 3);
+        }
+    }
+
+    #[test]
+    fn page_size_that_is_not_a_block_multiple_is_rejected() {
+        let dir = tempfile::TempDir::new().expect("temp dir can be created");
+        let path = dir.path().join("cryo.db");
+
+        let err = Pager::open_with_page_size(&path, 8, 1000)
+            .err()
+            .expect("page size of 1000 is rejected");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+
+        flushed_database(&path, 1024, 2);
+        let reopened = Pager::open_with_page_size(&path, 8, 1024)
+            .expect("block multiple page size reopens");
+        assert_eq!(reopened.page_size(), 1024);
+    }
+
+    #[test]
+    fn second_open_of_same_file_is_rejected_until_first_is_dropped() {
+        let dir = tempfile::TempDir::new().expect("temp dir can be created");
+        let path = dir.path().join("cryo.db");
+
+        let first = Pager::open(&path, 8).expect("first open succeeds");
+        let err = Pager::open(&path, 8)
+            .err()
+            .expect("second open is rejected");
+        assert_eq!(err.kind(), io::ErrorKind::ResourceBusy);
+        assert!(
+            err.to_string()
+                .contains("already in use")
+        );
+
+        first
+            .mut_page(
+                ROOT_PAGE_ID,
+                AccessContext::maintenance("test mutation"),
+                |page| page.set_num_keys(4),
+            )
+            .expect("root can be mutated");
+        first
+            .clear_cache()
+            .expect("root can be flushed to the file");
+        drop(first);
+
+        let reopened =
+            Pager::open(&path, 8).expect("file can be reopened once released");
+        let num_keys = reopened
+            .page(ROOT_PAGE_ID, AccessContext::anonymous(), |page| {
+                page.num_keys()
+            })
+            .expect("flushed root can be read back");
+        assert_eq!(num_keys, 4);
+    }
 }
@@ -1202,6 +1202,61 @@ mod tests {
         assert_eq!(last_lsn, begin_lsn);
     }
 
+    #[test]
+    fn open_ignores_trailing_frame_with_bad_checksum() {
+        let dir = TempDir::new().expect("temp dir can be created");
+        let path = generation_path(dir.path(), 0);
+
+        let commit_lsn;
+        {
+            let logger =
+                Logger::open(dir.path()).expect("logger can be created");
+            let begin_lsn = logger
+                .append(Record::Begin {
+                    txn_id: 1,
+                    prev_lsn: None,
+                })
+                .expect("begin can be appended");
+            commit_lsn = logger
+                .append(Record::Commit {
+                    txn_id: 1,
+                    prev_lsn: Some(begin_lsn.into()),
+                })
+                .expect("commit can be appended");
+            logger
+                .flush_through(commit_lsn)
+                .expect("records can be flushed");
+            logger
+                .sync_all()
+                .expect("records can be synced");
+        }
+
+        // Append a complete copy of the last frame with a corrupted payload.
+        let mut bytes = std::fs::read(&path).expect("wal file can be read");
+        let valid_len = bytes.len();
+        let mut garbage = bytes[commit_lsn.offset() as usize..].to_vec();
+        let last = garbage.len() - 1;
+        garbage[last] ^= 0xff;
+        bytes.extend_from_slice(&garbage);
+        std::fs::write(&path, bytes).expect("wal file can be written");
+
+        let reopened =
+            Logger::open(dir.path()).expect("logger recovers past bad tail");
+        assert_eq!(
+            reopened
+                .flushed_lsn()
+                .unwrap(),
+            Some(commit_lsn)
+        );
+        assert_eq!(reopened.next_lsn().unwrap(), Lsn::new(0, valid_len as u32));
+
+        let records = reopened
+            .read_all()
+            .expect("valid prefix can be read");
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].record.kind(), "commit");
+    }
+
     #[test]
     fn get_retrieves_flushed_and_buffered_records_by_lsn() {
         let (_dir, logger) = temp_logger();